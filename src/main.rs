@@ -1,39 +1,88 @@
 use std::{
+	cell::RefCell,
 	error::Error,
-	fmt::Display,
+	fmt::{self, Display},
 	fs::{self, File},
-	io::{self, BufReader},
+	io::{self, BufReader, Cursor, Read, Seek, Write},
 	ops::Sub,
 	path::{self, Path, PathBuf},
+	process,
 	time::{Duration, Instant, SystemTime},
 };
 
-use clap::Parser;
-use profile::{Profile, ProfileError};
-use wildmatch::WildMatch;
-use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+use clap::{Parser, Subcommand};
+use profile::{Compression, Profile, ProfileError};
+use rule::Rule;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
 mod profile;
+mod rule;
 
-/// Performs archiving on directories using profiles.
+/// Performs archiving and extraction on directories using profiles.
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about)]
 struct Args {
-	/// Specifies the profile file
-	#[arg(short, long)]
-	profile: PathBuf,
+	#[command(subcommand)]
+	command: Command,
+}
 
-	/// Specifies the output file
-	#[arg(short, long)]
-	file: PathBuf,
+/// Represents an available subcommand.
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Creates an archive from a profile
+	Archive {
+		/// Specifies the profile file
+		#[arg(short, long)]
+		profile: PathBuf,
+
+		/// Specifies the output file; required unless `--stdout` is given
+		#[arg(short, long)]
+		file: Option<PathBuf>,
+
+		/// Streams the finished archive to standard output instead of to a file
+		#[arg(long)]
+		stdout: bool,
+	},
+
+	/// Extracts an archive back onto the filesystem
+	Extract {
+		/// Specifies the archive file
+		#[arg(short, long)]
+		file: PathBuf,
+
+		/// Specifies the output directory
+		#[arg(short, long)]
+		output: PathBuf,
+	},
 }
 
 fn main() {
 	let args = Args::parse();
 
-	match archive(args.profile, args.file) {
-		Ok(()) => println!("Successfully archived profile."),
-		Err(e) => println!("Failed to archive profile: {}.", e),
+	match args.command {
+		Command::Archive { profile, file, stdout } => {
+			// Status messages go to standard error when the archive itself is streamed to standard output.
+			let report = |message: &str| if stdout { eprintln!("{}", message) } else { println!("{}", message) };
+
+			match archive(profile, file, stdout) {
+				Ok(summary) if summary.failures.is_empty() => report("Successfully archived profile."),
+				Ok(summary) => {
+					report(&format!("Archived profile with {} failed entries.", summary.failures.len()));
+					process::exit(1);
+				}
+				Err(e) => {
+					report(&format!("Failed to archive profile: {}.", e));
+					process::exit(1);
+				}
+			}
+		}
+		Command::Extract { file, output } => match extract(file, output) {
+			Ok(()) => println!("Successfully extracted archive."),
+			Err(e) => {
+				println!("Failed to extract archive: {}.", e);
+				process::exit(1);
+			}
+		},
 	}
 }
 
@@ -44,19 +93,22 @@ enum ArchiveError {
 	FailedToLoad(ProfileError),
 
 	/// Indicates that the metadata for a particular path could not be read.
-	FailedToInspectPath(io::Error),
+	FailedToInspectPath(PathBuf, io::Error),
 
 	/// Indicates that the initial archive file could not be created.
-	FailedToCreateArchive(io::Error),
+	FailedToCreateArchive(PathBuf, io::Error),
 
 	/// Indicates that a particular directory could not be read for its files.
-	FailedToReadDirectory(io::Error),
+	FailedToReadDirectory(PathBuf, io::Error),
 
 	/// Indicates that a particular file could not be read for its contents.
-	FailedToReadFile(io::Error),
+	FailedToReadFile(PathBuf, io::Error),
+
+	/// Indicates that a particular symlink's target could not be read.
+	FailedToReadLink(PathBuf, io::Error),
 
 	/// Indicates that a specific file could not be copied to the archive.
-	FailedToCopyFile(io::Error),
+	FailedToCopyFile(PathBuf, io::Error),
 
 	/// Indicates that a new entry could not be marked in the archive.
 	FailedToMarkEntry(zip::result::ZipError),
@@ -69,6 +121,12 @@ enum ArchiveError {
 
 	/// Indicates that the shared parent path between entries could not be determined.
 	FailedToDetermineParentPath,
+
+	/// Indicates that neither an output file nor `--stdout` were given.
+	MissingOutput,
+
+	/// Indicates that the finished archive could not be written to standard output.
+	FailedToWriteStdout(io::Error),
 }
 
 type ArchiveResult = Result<(), ArchiveError>;
@@ -77,40 +135,97 @@ impl Display for ArchiveError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Self::FailedToLoad(e) => write!(f, "failed to load profile [{}]", e),
-			Self::FailedToInspectPath(e) => write!(f, "failed to inspect path [{}]", e),
-			Self::FailedToCreateArchive(e) => write!(f, "failed to create archive file [{}]", e),
-			Self::FailedToReadDirectory(e) => write!(f, "failed to read directory [{}]", e),
-			Self::FailedToReadFile(e) => write!(f, "failed to read file [{}]", e),
-			Self::FailedToCopyFile(e) => write!(f, "failed to copy file to archive [{}]", e),
+			Self::FailedToInspectPath(p, e) => write!(f, "failed to inspect path <{}> [{}]", p.display(), e),
+			Self::FailedToCreateArchive(p, e) => write!(f, "failed to create archive file <{}> [{}]", p.display(), e),
+			Self::FailedToReadDirectory(p, e) => write!(f, "failed to read directory <{}> [{}]", p.display(), e),
+			Self::FailedToReadFile(p, e) => write!(f, "failed to read file <{}> [{}]", p.display(), e),
+			Self::FailedToReadLink(p, e) => write!(f, "failed to read symlink target <{}> [{}]", p.display(), e),
+			Self::FailedToCopyFile(p, e) => write!(f, "failed to copy file <{}> to archive [{}]", p.display(), e),
 			Self::FailedToMarkEntry(e) => write!(f, "failed to mark entry in archive [{}]", e),
 			Self::FailedToFinishArchive(e) => write!(f, "failed to finish archive [{}]", e),
 			Self::FailedToStripPrefix(e) => write!(f, "failed to strip prefix [{}]", e),
 			Self::FailedToDetermineParentPath => write!(f, "failed to determine shared parent path"),
+			Self::MissingOutput => write!(f, "neither an output file nor --stdout were given"),
+			Self::FailedToWriteStdout(e) => write!(f, "failed to write archive to standard output [{}]", e),
 		}
 	}
 }
 
-impl Error for ArchiveError {}
+impl Error for ArchiveError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::FailedToLoad(e) => Some(e),
+			Self::FailedToInspectPath(_, e) => Some(e),
+			Self::FailedToCreateArchive(_, e) => Some(e),
+			Self::FailedToReadDirectory(_, e) => Some(e),
+			Self::FailedToReadFile(_, e) => Some(e),
+			Self::FailedToReadLink(_, e) => Some(e),
+			Self::FailedToCopyFile(_, e) => Some(e),
+			Self::FailedToMarkEntry(e) => Some(e),
+			Self::FailedToFinishArchive(e) => Some(e),
+			Self::FailedToStripPrefix(e) => Some(e),
+			Self::FailedToDetermineParentPath => None,
+			Self::MissingOutput => None,
+			Self::FailedToWriteStdout(e) => Some(e),
+		}
+	}
+}
+
+/// Represents a single entry that could not be archived, recording the path involved alongside the cause.
+struct Failure {
+	path: PathBuf,
+	error: ArchiveError,
+}
+
+/// Represents the outcome of an archiving run, including any entries that were skipped along the way.
+struct ArchiveSummary {
+	failures: Vec<Failure>,
+}
 
 struct Ctx<'a> {
 	profile: &'a Profile,
-	ignores: &'a [WildMatch],
+	ignores: &'a [Rule],
+	compression: Compression,
+	failures: RefCell<Vec<Failure>>,
+
+	/// Whether progress messages should be sent to standard error, because standard output is the archive itself.
+	quiet: bool,
+}
+
+/// Emits a progress message, routing it to standard error instead of standard output when the archive itself is
+/// being streamed to standard output.
+fn progress(ctx: &Ctx, args: fmt::Arguments) {
+	if ctx.quiet {
+		eprintln!("{}", args);
+	} else {
+		println!("{}", args);
+	}
 }
 
-/// Archives the entries described by the specified profile to the specified file.
-fn archive<T, V>(profile: T, file: V) -> ArchiveResult
+/// Archives the entries described by the specified profile to the specified file, or to standard output when
+/// `stdout` is set (in which case `file` is not required).
+fn archive<T>(profile: T, file: Option<PathBuf>, stdout: bool) -> Result<ArchiveSummary, ArchiveError>
 where
 	T: AsRef<Path>,
-	V: AsRef<Path>,
 {
-	println!("Loading profile from path <{}>...", profile.as_ref().display());
+	// Since the finished archive itself may be streamed to standard output, progress messages are sent to standard
+	// error instead in that case, so they don't corrupt the archive byte stream.
+
+	if stdout {
+		eprintln!("Loading profile from path <{}>...", profile.as_ref().display());
+	} else {
+		println!("Loading profile from path <{}>...", profile.as_ref().display());
+	}
 
 	let profile = Profile::load(profile).map_err(ArchiveError::FailedToLoad)?;
 
-	println!("Creating archive using profile '{}'...", profile.name);
+	if stdout {
+		eprintln!("Creating archive using profile '{}'...", profile.name);
+	} else {
+		println!("Creating archive using profile '{}'...", profile.name);
+	}
 
 	let start = Instant::now();
-	let file = File::create(file).map_err(ArchiveError::FailedToCreateArchive)?;
 
 	// Determine the shared parent path and ignores.
 
@@ -124,61 +239,253 @@ where
 		.ok_or(ArchiveError::FailedToDetermineParentPath)?;
 
 	#[rustfmt::skip]
-	let ignores: Vec<WildMatch> = profile.ignores.iter()
-		.map(|i| WildMatch::new(i))
+	let ignores: Vec<Rule> = profile.ignores.iter()
+		.map(|i| Rule::parse(i))
 		.collect();
 
-	let mut writer = ZipWriter::new(file);
-
-	// Iterate and archive each directory and its contents.
-
 	let ctx = Ctx {
 		profile: &profile,
 		ignores: &ignores,
+		compression: profile.compression(),
+		failures: RefCell::new(Vec::new()),
+		quiet: stdout,
 	};
 
-	println!("Archiving {} directories...", ctx.profile.directories.len());
+	progress(&ctx, format_args!("Archiving {} directories...", ctx.profile.directories.len()));
 
-	for dir in &ctx.profile.directories {
-		println!("Archiving directory <{}>...", dir.display());
+	// Since `ZipWriter` requires `Seek`, buffer the archive into memory before streaming it to standard output;
+	// otherwise write it directly to the destination file.
+
+	if stdout {
+		let writer = ZipWriter::new(Cursor::new(Vec::new()));
+		let cursor = run_archive(writer, &ctx, parent)?;
 
-		if let Err(e) = compress_dir(&mut writer, &ctx, &parent, dir) {
-			println!("Failed to archive directory: {}.", e);
+		io::stdout().write_all(cursor.get_ref()).map_err(ArchiveError::FailedToWriteStdout)?;
+	} else {
+		let path = file.ok_or(ArchiveError::MissingOutput)?;
+		let output = File::create(&path).map_err(|e| ArchiveError::FailedToCreateArchive(path, e))?;
+
+		run_archive(ZipWriter::new(output), &ctx, parent)?;
+	}
+
+	progress(&ctx, format_args!("Created and finished archive in {:#?}.", start.elapsed()));
+
+	// Summarise every entry that was skipped along the way, rather than letting the failures scroll past mid-run.
+
+	let failures = ctx.failures.take();
+
+	if !failures.is_empty() {
+		progress(&ctx, format_args!("Failed to archive {} entries:", failures.len()));
+
+		for failure in &failures {
+			progress(&ctx, format_args!(" - <{}>: {}", failure.path.display(), failure.error));
 		}
 	}
 
-	// Finish the resulting archive.
+	Ok(ArchiveSummary { failures })
+}
+
+/// Archives every directory described by the profile's context into the specified writer, finishing and returning
+/// the underlying sink once done.
+fn run_archive<W>(mut writer: ZipWriter<W>, ctx: &Ctx, parent: &Path) -> Result<W, ArchiveError>
+where
+	W: Write + Seek,
+{
+	for dir in &ctx.profile.directories {
+		progress(ctx, format_args!("Archiving directory <{}>...", dir.display()));
+
+		if let Err(e) = compress_dir(&mut writer, ctx, parent, dir) {
+			ctx.failures.borrow_mut().push(Failure { path: dir.clone(), error: e });
+		}
+	}
 
-	println!("Finishing archive...");
+	progress(ctx, format_args!("Finishing archive..."));
 
 	writer.set_comment(format!("Directory Archiver [{}]", &ctx.profile.name));
-	writer.finish().map_err(ArchiveError::FailedToFinishArchive)?;
+	writer.finish().map_err(ArchiveError::FailedToFinishArchive)
+}
+
+/// Represents an extraction-related error.
+#[derive(Debug)]
+enum ExtractError {
+	/// Indicates that the archive file could not be opened.
+	FailedToOpenArchive(io::Error),
+
+	/// Indicates that the archive could not be read as a valid ZIP.
+	FailedToReadArchive(zip::result::ZipError),
+
+	/// Indicates that a particular entry within the archive could not be read.
+	FailedToReadEntry(zip::result::ZipError),
+
+	/// Indicates that the output directory could not be created.
+	FailedToCreateOutputDir(io::Error),
+
+	/// Indicates that a particular entry could not be written to the filesystem.
+	FailedToWriteFile(io::Error),
+
+	/// Indicates that an entry's path would escape the chosen output directory.
+	PathTraversalRejected(PathBuf),
+}
+
+type ExtractResult = Result<(), ExtractError>;
+
+impl Display for ExtractError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::FailedToOpenArchive(e) => write!(f, "failed to open archive file [{}]", e),
+			Self::FailedToReadArchive(e) => write!(f, "failed to read archive [{}]", e),
+			Self::FailedToReadEntry(e) => write!(f, "failed to read entry [{}]", e),
+			Self::FailedToCreateOutputDir(e) => write!(f, "failed to create output directory [{}]", e),
+			Self::FailedToWriteFile(e) => write!(f, "failed to write file [{}]", e),
+			Self::PathTraversalRejected(p) => write!(f, "path traversal rejected for entry destined for <{}>", p.display()),
+		}
+	}
+}
+
+impl Error for ExtractError {}
+
+/// Extracts the archive at the specified file back onto the filesystem, rooted at the specified output directory.
+fn extract<T, V>(file: T, output: V) -> ExtractResult
+where
+	T: AsRef<Path>,
+	V: AsRef<Path>,
+{
+	println!("Opening archive from path <{}>...", file.as_ref().display());
+
+	let start = Instant::now();
+	let file = File::open(file).map_err(ExtractError::FailedToOpenArchive)?;
+	let mut archive = ZipArchive::new(file).map_err(ExtractError::FailedToReadArchive)?;
+
+	let output = output.as_ref();
+
+	fs::create_dir_all(output).map_err(ExtractError::FailedToCreateOutputDir)?;
+
+	let root = fs::canonicalize(output).map_err(ExtractError::FailedToCreateOutputDir)?;
+
+	println!("Extracting {} entries to <{}>...", archive.len(), output.display());
+
+	// Recreate directory entries first, then stream file entries, guarding against Zip-Slip on every entry.
+
+	let mut indices: Vec<usize> = (0..archive.len()).collect();
+	indices.sort_by_key(|&i| !archive.by_index(i).is_ok_and(|entry| entry.is_dir()));
+
+	for i in indices {
+		let mut entry = archive.by_index(i).map_err(ExtractError::FailedToReadEntry)?;
+
+		// Resolve the entry's stored path lexically against the output root *before* touching the filesystem, so an
+		// escaping `..` component (or an absolute path) is rejected before any directory is created for it.
+		let destination = resolve_within(&root, entry.name()).ok_or_else(|| ExtractError::PathTraversalRejected(output.join(entry.name())))?;
+
+		let parent = destination.parent().unwrap_or(output);
+		fs::create_dir_all(parent).map_err(ExtractError::FailedToCreateOutputDir)?;
+
+		// ZIP doesn't have a native symlink entry type; `compress_symlink` marks one (via `add_symlink`) by storing the
+		// Unix symlink bits in the entry's external attributes, with the link target as the entry's content.
+		let is_symlink = entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000);
+
+		if entry.is_dir() {
+			println!("Creating directory <{}>...", destination.display());
+
+			fs::create_dir_all(&destination).map_err(ExtractError::FailedToCreateOutputDir)?;
+		} else if is_symlink {
+			println!("Creating symlink <{}>...", destination.display());
+
+			let mut target = String::new();
+			entry.read_to_string(&mut target).map_err(ExtractError::FailedToWriteFile)?;
+
+			if destination.symlink_metadata().is_ok() {
+				fs::remove_file(&destination).map_err(ExtractError::FailedToWriteFile)?;
+			}
 
-	println!("Created and finished archive in {:#?}.", start.elapsed());
+			create_symlink(target, &destination).map_err(ExtractError::FailedToWriteFile)?;
+		} else {
+			println!("Extracting file <{}>...", destination.display());
+
+			let mut writer = File::create(&destination).map_err(ExtractError::FailedToWriteFile)?;
+
+			io::copy(&mut entry, &mut writer).map_err(ExtractError::FailedToWriteFile)?;
+		}
+	}
+
+	println!("Extracted archive in {:#?}.", start.elapsed());
 
 	Ok(())
 }
 
+/// Lexically resolves `relative` against `root` without touching the filesystem, rejecting any absolute path or any
+/// `..` component that would escape above `root`. Used to guard against Zip-Slip before any directory is created.
+fn resolve_within<T>(root: T, relative: &str) -> Option<PathBuf>
+where
+	T: AsRef<Path>,
+{
+	let mut resolved = root.as_ref().to_path_buf();
+	let mut depth = 0usize;
+
+	for component in Path::new(relative).components() {
+		match component {
+			path::Component::Normal(part) => {
+				resolved.push(part);
+				depth += 1;
+			}
+			path::Component::ParentDir => {
+				if depth == 0 {
+					return None;
+				}
+
+				resolved.pop();
+				depth -= 1;
+			}
+			path::Component::CurDir => {}
+			path::Component::RootDir | path::Component::Prefix(_) => return None,
+		}
+	}
+
+	Some(resolved)
+}
+
+/// Creates a symlink at `destination` pointing at `target`, as recreated from a `compress_symlink` entry.
+#[cfg(unix)]
+fn create_symlink<T, V>(target: T, destination: V) -> io::Result<()>
+where
+	T: AsRef<Path>,
+	V: AsRef<Path>,
+{
+	std::os::unix::fs::symlink(target, destination)
+}
+
+/// Symlink entries can only be recreated as actual symlinks on Unix-like platforms.
+#[cfg(not(unix))]
+fn create_symlink<T, V>(_target: T, _destination: V) -> io::Result<()>
+where
+	T: AsRef<Path>,
+	V: AsRef<Path>,
+{
+	Err(io::Error::new(io::ErrorKind::Unsupported, "extracting symlink entries is only supported on Unix-like platforms"))
+}
+
 /// Attempts to recursively compress the specified sub-directory to the specified writer.
-fn compress_dir<T, V>(writer: &mut ZipWriter<File>, ctx: &Ctx, parent: T, dir: V) -> ArchiveResult
+fn compress_dir<W, T, V>(writer: &mut ZipWriter<W>, ctx: &Ctx, parent: T, dir: V) -> ArchiveResult
 where
+	W: Write + Seek,
 	T: AsRef<Path>,
 	V: AsRef<Path>,
 {
-	if is_ignored(ctx, &dir) {
+	let path = dir.as_ref().strip_prefix(&parent).map_err(ArchiveError::FailedToStripPrefix)?;
+
+	if is_ignored(ctx, path, true) {
 		return Ok(());
 	}
 
-	let entries = fs::read_dir(&dir).map_err(ArchiveError::FailedToReadDirectory)?.flatten();
-	let path = dir.as_ref().strip_prefix(&parent).map_err(ArchiveError::FailedToStripPrefix)?;
+	let dir_path = dir.as_ref().to_path_buf();
+	let entries = fs::read_dir(&dir).map_err(|e| ArchiveError::FailedToReadDirectory(dir_path, e))?.flatten();
 
 	#[allow(deprecated)]
 	writer
 		.add_directory_from_path(
 			path,
 			FileOptions::default()
-				.compression_method(CompressionMethod::Bzip2)
-				.compression_level(Some(9))
+				.compression_method(ctx.compression.method)
+				.compression_level(ctx.compression.level)
 				.last_modified_time(to_last_modified_time(path)),
 		)
 		.map_err(ArchiveError::FailedToMarkEntry)?;
@@ -187,16 +494,25 @@ where
 
 	for entry in entries {
 		let path = entry.path();
+		let file_type = entry.file_type().map_err(|e| ArchiveError::FailedToInspectPath(path.clone(), e))?;
 
-		match entry.metadata().map_err(ArchiveError::FailedToInspectPath)? {
+		if file_type.is_symlink() && !ctx.profile.follow_symlinks {
+			if let Err(e) = compress_symlink(writer, ctx, parent.as_ref(), &path) {
+				ctx.failures.borrow_mut().push(Failure { path: path.clone(), error: e });
+			}
+
+			continue;
+		}
+
+		match entry.metadata().map_err(|e| ArchiveError::FailedToInspectPath(path.clone(), e))? {
 			m if m.is_dir() => {
 				if let Err(e) = compress_dir(writer, ctx, parent.as_ref(), &path) {
-					println!("Failed to compress sub-directory <{}>: {}.", path.display(), e);
+					ctx.failures.borrow_mut().push(Failure { path: path.clone(), error: e });
 				}
 			}
 			m if m.is_file() => {
 				if let Err(e) = compress_file(writer, ctx, parent.as_ref(), &path) {
-					println!("Failed to compress sub-file <{}>: {}.", path.display(), e);
+					ctx.failures.borrow_mut().push(Failure { path: path.clone(), error: e });
 				}
 			}
 			_ => {}
@@ -206,21 +522,58 @@ where
 	Ok(())
 }
 
-/// Attempts to compress the specified sub-file to the specified writer.
-fn compress_file<T, V>(writer: &mut ZipWriter<File>, ctx: &Ctx, parent: T, file: V) -> ArchiveResult
+/// Attempts to archive the specified symbolic link as a dedicated symlink entry, storing its target path as the
+/// entry's contents rather than following it and duplicating the data it points to.
+fn compress_symlink<W, T, V>(writer: &mut ZipWriter<W>, ctx: &Ctx, parent: T, link: V) -> ArchiveResult
 where
+	W: Write + Seek,
 	T: AsRef<Path>,
 	V: AsRef<Path>,
 {
-	if is_ignored(ctx, &file) {
+	let path = link.as_ref().strip_prefix(&parent).map_err(ArchiveError::FailedToStripPrefix)?;
+
+	if is_ignored(ctx, path, false) {
 		return Ok(());
 	}
 
-	println!("Compressing file <{}>...", file.as_ref().display());
+	progress(ctx, format_args!("Compressing symlink <{}>...", link.as_ref().display()));
+
+	let link_path = link.as_ref().to_path_buf();
+	let target = fs::read_link(&link).map_err(|e| ArchiveError::FailedToReadLink(link_path.clone(), e))?;
+
+	// `FileOptions::unix_permissions` keeps only the permission bits, so the symlink file type is set by `add_symlink`,
+	// which also stores the link target as the entry's contents.
+	let name = path.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+
+	writer
+		.add_symlink(name, target.to_string_lossy(), FileOptions::default().last_modified_time(to_last_modified_time(path)))
+		.map_err(ArchiveError::FailedToMarkEntry)?;
+
+	Ok(())
+}
 
-	let entry = File::open(&file).map_err(ArchiveError::FailedToReadFile)?;
+/// Attempts to compress the specified sub-file to the specified writer.
+fn compress_file<W, T, V>(writer: &mut ZipWriter<W>, ctx: &Ctx, parent: T, file: V) -> ArchiveResult
+where
+	W: Write + Seek,
+	T: AsRef<Path>,
+	V: AsRef<Path>,
+{
 	let path = file.as_ref().strip_prefix(&parent).map_err(ArchiveError::FailedToStripPrefix)?;
 
+	if is_ignored(ctx, path, false) {
+		return Ok(());
+	}
+
+	progress(ctx, format_args!("Compressing file <{}>...", file.as_ref().display()));
+
+	let file_path = file.as_ref().to_path_buf();
+	let entry = File::open(&file).map_err(|e| ArchiveError::FailedToReadFile(file_path.clone(), e))?;
+
+	// Enable ZIP64 extensions for this entry alone when its size exceeds (or cannot be determined against) the 32-bit limit.
+
+	let large_file = entry.metadata().map(|m| m.len() > u32::MAX as u64).unwrap_or(true);
+
 	// Compress the entry.
 
 	let mut reader = BufReader::new(entry);
@@ -230,13 +583,14 @@ where
 		.start_file_from_path(
 			path,
 			FileOptions::default()
-				.compression_method(CompressionMethod::Bzip2)
-				.compression_level(Some(9))
-				.last_modified_time(to_last_modified_time(path)),
+				.compression_method(ctx.compression.method)
+				.compression_level(ctx.compression.level)
+				.last_modified_time(to_last_modified_time(path))
+				.large_file(large_file),
 		)
 		.map_err(ArchiveError::FailedToMarkEntry)?;
 
-	io::copy(&mut reader, writer).map_err(ArchiveError::FailedToCopyFile)?;
+	io::copy(&mut reader, writer).map_err(|e| ArchiveError::FailedToCopyFile(file_path, e))?;
 
 	Ok(())
 }
@@ -267,17 +621,104 @@ where
 	last_modified
 }
 
-/// Determines if the specified path is ignored at all by the specified context.
-fn is_ignored<T>(ctx: &Ctx, path: T) -> bool
+/// Determines if the specified path, relative to the shared parent, is excluded by the specified context's rules.
+fn is_ignored<T>(ctx: &Ctx, path: T, is_dir: bool) -> bool
 where
 	T: AsRef<Path>,
 {
-	#[rustfmt::skip]
-	let ignored = path.as_ref()
-		.file_name()
-		.and_then(|name| name.to_str())
-		.map(|name| ctx.ignores.iter().any(|ignore| ignore.matches(name)))
-		.unwrap_or(false);
+	rule::is_excluded(ctx.ignores, path, is_dir)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::SeekFrom;
+
+	use super::*;
 
-	ignored
+	/// An entry whose stored name escapes the output directory via `..` components is rejected before extraction
+	/// writes anything outside of it.
+	#[test]
+	fn extract_rejects_path_traversal_entry() {
+		let root = std::env::temp_dir().join(format!("directory-archiver-zip-slip-{}", process::id()));
+		fs::remove_dir_all(&root).ok();
+		fs::create_dir_all(&root).expect("failed to create fixture directory");
+
+		let archive_path = root.join("malicious.zip");
+		let output = root.join("output");
+
+		{
+			let file = File::create(&archive_path).expect("failed to create archive fixture");
+			let mut writer = ZipWriter::new(file);
+
+			writer.start_file("../../evil.txt", FileOptions::default()).expect("failed to start malicious entry");
+			writer.write_all(b"evil").expect("failed to write malicious entry");
+			writer.finish().expect("failed to finish archive");
+		}
+
+		let result = extract(&archive_path, &output);
+
+		assert!(matches!(result, Err(ExtractError::PathTraversalRejected(_))));
+
+		// "../../evil.txt" resolved against `output` (two levels deep under the system temp directory) would escape
+		// to the temp directory itself if the guard failed to catch it before creating anything on disk.
+		let escaped = std::env::temp_dir().join("evil.txt");
+		assert!(!escaped.exists());
+
+		fs::remove_dir_all(&root).ok();
+		fs::remove_file(&escaped).ok();
+	}
+
+	/// Compresses a sparse fixture larger than the 32-bit ZIP size limit and confirms the entry round-trips through
+	/// `ZipArchive` with its full uncompressed size, verifying the ZIP64 extension kicks in for such entries.
+	///
+	/// Writes the archive to a real temp file rather than an in-memory `Cursor` to avoid forcing a multi-gigabyte
+	/// heap allocation, but this still streams a multi-gigabyte fixture, so it's ignored by default; run it
+	/// explicitly with `cargo test -- --ignored`.
+	#[test]
+	#[ignore]
+	fn compress_file_enables_zip64_for_large_files() {
+		let dir = std::env::temp_dir().join(format!("directory-archiver-large-file-{}", process::id()));
+		fs::create_dir_all(&dir).expect("failed to create fixture directory");
+
+		let size = u32::MAX as u64 + 4096;
+		let path = dir.join("large.bin");
+
+		{
+			let mut file = File::create(&path).expect("failed to create sparse fixture");
+			file.seek(SeekFrom::Start(size - 1)).expect("failed to seek sparse fixture");
+			file.write_all(&[0]).expect("failed to write sparse fixture");
+		}
+
+		let profile = Profile {
+			name: "test".to_owned(),
+			directories: vec![dir.clone()],
+			ignores: Vec::new(),
+			compression_method: Some("stored".to_owned()),
+			compression_level: None,
+			follow_symlinks: false,
+		};
+
+		let ctx = Ctx {
+			profile: &profile,
+			ignores: &[],
+			compression: profile.compression(),
+			failures: RefCell::new(Vec::new()),
+			quiet: true,
+		};
+
+		let archive_path = dir.join("large.zip");
+		let archive_file = File::create(&archive_path).expect("failed to create archive fixture");
+
+		let mut writer = ZipWriter::new(archive_file);
+		compress_file(&mut writer, &ctx, &dir, &path).expect("failed to compress large fixture");
+		writer.finish().expect("failed to finish archive");
+
+		let archive_file = File::open(&archive_path).expect("failed to reopen archive fixture");
+		let mut archive = ZipArchive::new(archive_file).expect("failed to read archive back");
+		let entry = archive.by_index(0).expect("failed to read entry back");
+
+		assert_eq!(entry.size(), size);
+
+		fs::remove_dir_all(&dir).ok();
+	}
 }
@@ -1,57 +1,118 @@
-use core::fmt;
-use std::{
-	error::Error,
-	fmt::Display,
-	fs, io,
-	path::{Path, PathBuf},
-};
-
-use serde::Deserialize;
-
-/// Represents a profile.
-#[derive(Debug, Deserialize)]
-pub struct Profile {
-	/// The display name.
-	pub name: String,
-
-	/// The paths of directories that will be included.
-	pub directories: Vec<PathBuf>,
-
-	/// The wildcard patterns for directory names and file names that should be ignored.
-	pub ignores: Vec<String>,
-}
-
-/// Represents a profile-related error.
-#[derive(Debug)]
-pub enum ProfileError {
-	/// Indicates that a profile could not be read.
-	FailedToRead(io::Error),
-
-	/// Indicates that the JSON representing a profile could not be parsed.
-	FailedToDeserialise(serde_json::Error),
-}
-
-pub type ProfileResult = Result<Profile, ProfileError>;
-
-impl Profile {
-	pub fn load<T>(path: T) -> ProfileResult
-	where
-		T: AsRef<Path>,
-	{
-		let json = fs::read_to_string(&path).map_err(ProfileError::FailedToRead)?;
-		let profile = serde_json::from_str(&json).map_err(ProfileError::FailedToDeserialise)?;
-
-		Ok(profile)
-	}
-}
-
-impl Error for ProfileError {}
-
-impl Display for ProfileError {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		match self {
-			Self::FailedToRead(e) => write!(f, "failed to read file [{}]", e),
-			Self::FailedToDeserialise(e) => write!(f, "failed to deserialise value [{}]", e),
-		}
-	}
-}
+use core::fmt;
+use std::{
+	error::Error,
+	fmt::Display,
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use zip::CompressionMethod;
+
+/// Represents a profile.
+#[derive(Debug, Deserialize)]
+pub struct Profile {
+	/// The display name.
+	pub name: String,
+
+	/// The paths of directories that will be included.
+	pub directories: Vec<PathBuf>,
+
+	/// The ordered include/exclude rules, matched against paths relative to the shared parent (a leading `!`
+	/// re-includes, a trailing `/` matches directories only, and a leading `/` anchors to the archive root).
+	pub ignores: Vec<String>,
+
+	/// The compression method to use, if specified (one of "stored", "deflated", "bzip2" or "zstd").
+	#[serde(default)]
+	pub compression_method: Option<String>,
+
+	/// The compression level to use, if specified.
+	#[serde(default)]
+	pub compression_level: Option<i32>,
+
+	/// Whether symbolic links should be followed and archived as their target's contents, rather than as links.
+	#[serde(default)]
+	pub follow_symlinks: bool,
+}
+
+/// Represents the resolved compression settings for a profile.
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+	/// The compression method to use.
+	pub method: CompressionMethod,
+
+	/// The compression level to use, if any.
+	pub level: Option<i32>,
+}
+
+/// Represents a profile-related error.
+#[derive(Debug)]
+pub enum ProfileError {
+	/// Indicates that a profile could not be read.
+	FailedToRead(io::Error),
+
+	/// Indicates that the JSON representing a profile could not be parsed.
+	FailedToDeserialise(serde_json::Error),
+
+	/// Indicates that the profile specified an unrecognised compression method.
+	UnknownCompressionMethod(String),
+}
+
+pub type ProfileResult = Result<Profile, ProfileError>;
+
+impl Profile {
+	pub fn load<T>(path: T) -> ProfileResult
+	where
+		T: AsRef<Path>,
+	{
+		let json = fs::read_to_string(&path).map_err(ProfileError::FailedToRead)?;
+		let profile: Profile = serde_json::from_str(&json).map_err(ProfileError::FailedToDeserialise)?;
+
+		if let Some(method) = &profile.compression_method {
+			parse_compression_method(method).ok_or_else(|| ProfileError::UnknownCompressionMethod(method.clone()))?;
+		}
+
+		Ok(profile)
+	}
+
+	/// Resolves the compression method and level for this profile, falling back to the previous Bzip2/9 default.
+	pub fn compression(&self) -> Compression {
+		let method = self
+			.compression_method
+			.as_deref()
+			.and_then(parse_compression_method)
+			.unwrap_or(CompressionMethod::Bzip2);
+
+		// `Stored` entries carry no compression level at all; the underlying `zip` crate rejects a level paired with
+		// `Stored` once a writer has switched out of its initial state, so never resolve one for it.
+		let level = match method {
+			CompressionMethod::Stored => None,
+			_ => self.compression_level.or(Some(9)),
+		};
+
+		Compression { method, level }
+	}
+}
+
+/// Parses a compression method name into its corresponding `CompressionMethod`, if recognised.
+fn parse_compression_method(method: &str) -> Option<CompressionMethod> {
+	match method {
+		"stored" => Some(CompressionMethod::Stored),
+		"deflated" => Some(CompressionMethod::Deflated),
+		"bzip2" => Some(CompressionMethod::Bzip2),
+		"zstd" => Some(CompressionMethod::Zstd),
+		_ => None,
+	}
+}
+
+impl Error for ProfileError {}
+
+impl Display for ProfileError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::FailedToRead(e) => write!(f, "failed to read file [{}]", e),
+			Self::FailedToDeserialise(e) => write!(f, "failed to deserialise value [{}]", e),
+			Self::UnknownCompressionMethod(m) => write!(f, "unknown compression method '{}'", m),
+		}
+	}
+}
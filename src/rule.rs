@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use wildmatch::WildMatch;
+
+/// Represents whether a rule includes or excludes a matching path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchType {
+	/// The path should be included, overriding any earlier exclusion.
+	Include,
+
+	/// The path should be excluded.
+	Exclude,
+}
+
+/// Represents a single ordered include/exclude rule, matched against a path relative to the shared parent.
+#[derive(Debug)]
+pub struct Rule {
+	kind: MatchType,
+	pattern: WildMatch,
+	directory_only: bool,
+	anchored: bool,
+}
+
+impl Rule {
+	/// Parses a single rule from its profile JSON representation.
+	///
+	/// A leading `!` marks the rule as a re-inclusion rather than an exclusion; a trailing `/` restricts the rule
+	/// to directories; a pattern starting with `/` is anchored to the archive root, while any other pattern is
+	/// unanchored and matches at any depth.
+	pub fn parse(raw: &str) -> Self {
+		let (kind, raw) = match raw.strip_prefix('!') {
+			Some(rest) => (MatchType::Include, rest),
+			None => (MatchType::Exclude, raw),
+		};
+
+		let (raw, directory_only) = match raw.strip_suffix('/') {
+			Some(rest) => (rest, true),
+			None => (raw, false),
+		};
+
+		let anchored = raw.starts_with('/');
+		let pattern = raw.strip_prefix('/').unwrap_or(raw);
+
+		Self {
+			kind,
+			pattern: WildMatch::new(pattern),
+			directory_only,
+			anchored,
+		}
+	}
+
+	/// Determines whether this rule matches the specified path, relative to the shared parent.
+	fn matches<T>(&self, relative: T, is_dir: bool) -> bool
+	where
+		T: AsRef<Path>,
+	{
+		if self.directory_only && !is_dir {
+			return false;
+		}
+
+		let relative = relative.as_ref();
+		let full = relative.to_string_lossy();
+
+		if self.anchored {
+			return self.pattern.matches(&full);
+		}
+
+		#[rustfmt::skip]
+		let name_matches = relative.file_name()
+			.map(|name| self.pattern.matches(&name.to_string_lossy()))
+			.unwrap_or(false);
+
+		name_matches || self.pattern.matches(&full)
+	}
+}
+
+/// Evaluates the specified ordered rules against a path relative to the shared parent, returning whether the path
+/// should be excluded from the archive. The last rule whose pattern matches decides the outcome, so a later
+/// re-inclusion rule can override an earlier exclusion; a path is included when no rule matches.
+pub fn is_excluded<T>(rules: &[Rule], relative: T, is_dir: bool) -> bool
+where
+	T: AsRef<Path>,
+{
+	#[rustfmt::skip]
+	let excluded = rules.iter().rev()
+		.find(|rule| rule.matches(&relative, is_dir))
+		.map(|rule| rule.kind == MatchType::Exclude)
+		.unwrap_or(false);
+
+	excluded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// An unanchored pattern matches a file by its base name at any depth.
+	#[test]
+	fn unanchored_pattern_matches_basename_at_depth() {
+		let rules = vec![Rule::parse("*.log")];
+
+		assert!(is_excluded(&rules, "output.log", false));
+		assert!(is_excluded(&rules, "nested/deep/output.log", false));
+		assert!(!is_excluded(&rules, "output.txt", false));
+	}
+
+	/// A pattern anchored with a leading `/` only matches against the full path from the archive root.
+	#[test]
+	fn anchored_pattern_matches_only_from_root() {
+		let rules = vec![Rule::parse("/build")];
+
+		assert!(is_excluded(&rules, "build", false));
+		assert!(!is_excluded(&rules, "nested/build", false));
+	}
+
+	/// A rule suffixed with `/` only excludes directories, never files with a matching name.
+	#[test]
+	fn directory_only_rule_does_not_match_files() {
+		let rules = vec![Rule::parse("target/")];
+
+		assert!(is_excluded(&rules, "target", true));
+		assert!(!is_excluded(&rules, "target", false));
+	}
+
+	/// A later re-inclusion rule (prefixed with `!`) overrides an earlier exclusion for a specific path.
+	#[test]
+	fn later_rule_wins_allowing_reinclusion() {
+		let rules = vec![Rule::parse("*.log"), Rule::parse("!keep.log")];
+
+		assert!(is_excluded(&rules, "debug.log", false));
+		assert!(!is_excluded(&rules, "keep.log", false));
+	}
+}